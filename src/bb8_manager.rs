@@ -0,0 +1,31 @@
+//! A `bb8::ManageConnection` implementation for [`BoltConnectionManager`], behind the `bb8`
+//! feature, for users who standardize on the `bb8` pool instead of `mobc`. Reuses the same
+//! handshake and RESET-aware liveness check the `mobc` [`Manager`] impl does.
+//!
+//! [`BoltConnectionManager`]: crate::BoltConnectionManager
+//! [`Manager`]: mobc::Manager
+
+use crate::{BoltConnectionManager, Error};
+use async_trait::async_trait;
+use bolt_client::{Client, Stream};
+use mobc::Manager;
+use tokio::io::BufStream;
+use tokio_util::compat::Compat;
+
+#[async_trait]
+impl bb8::ManageConnection for BoltConnectionManager {
+    type Connection = Client<Compat<BufStream<Stream>>>;
+    type Error = Error;
+
+    async fn connect(&self) -> Result<Self::Connection, Self::Error> {
+        Manager::connect(self).await
+    }
+
+    async fn is_valid(&self, conn: &mut Self::Connection) -> Result<(), Self::Error> {
+        self.validate(conn).await
+    }
+
+    fn has_broken(&self, conn: &mut Self::Connection) -> bool {
+        Self::is_broken(conn)
+    }
+}
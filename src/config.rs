@@ -0,0 +1,157 @@
+//! Builder-style configuration for [`BoltConnectionManager`], mirroring the
+//! `DriverConfig`/`ConnectionConfig` split a full Bolt driver exposes: connection establishment
+//! (encryption, timeouts, keepalive) kept apart from the fields describing the server itself.
+//!
+//! [`BoltConnectionManager`]: crate::BoltConnectionManager
+
+use crate::resolve::DefaultResolver;
+use crate::{AccessMode, BoltConnectionManager, Resolve, Target};
+use bolt_proto::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// TLS/encryption behavior for a [`BoltConnectionManager`]'s connections, decoupled from
+/// whether a `domain` happens to be configured.
+#[derive(Debug, Clone)]
+pub enum Encryption {
+    /// Connect over a plain TCP socket; `domain` is not used.
+    Disabled,
+    /// TLS using the platform's trusted root certificates.
+    SystemTrust,
+    /// TLS using the platform's trusted root certificates plus `der_certificates` as
+    /// additional trusted roots.
+    SystemTrustWithRoots { der_certificates: Vec<Vec<u8>> },
+}
+
+/// Builds a [`BoltConnectionManager`].
+///
+/// [`BoltConnectionManager`]: crate::BoltConnectionManager
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use bolt_proto::version::V4_1;
+/// # use mobc::{Manager, Pool};
+/// # use mobc_boltrs::{BoltConnectionManagerBuilder, Encryption};
+/// # use std::collections::HashMap;
+/// # use std::iter::FromIterator;
+/// # use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let manager = BoltConnectionManagerBuilder::new(
+///         "localhost:7687",
+///         [V4_1, 0, 0, 0],
+///         HashMap::from_iter(vec![
+///             ("user_agent", "bolt-client/X.Y.Z"),
+///             ("scheme", "basic"),
+///             ("principal", "username"),
+///             ("credentials", "password"),
+///         ]),
+///     )
+///     .domain("localhost")
+///     .encryption(Encryption::SystemTrust)
+///     .connect_timeout(Duration::from_secs(5))
+///     .keepalive(Duration::from_secs(60))
+///     .build();
+///
+///     let pool = Pool::builder().max_open(20).build(manager);
+///     let client = pool.get().await?;
+///
+/// #   Ok(())
+/// # }
+/// ```
+pub struct BoltConnectionManagerBuilder {
+    addr: String,
+    domain: Option<String>,
+    preferred_versions: [u32; 4],
+    metadata: HashMap<String, Value>,
+    encryption: Option<Encryption>,
+    connect_timeout: Option<Duration>,
+    keepalive: Option<Duration>,
+    resolver: Option<Arc<dyn Resolve>>,
+}
+
+impl BoltConnectionManagerBuilder {
+    /// Starts a builder for the address and, if applicable, preferred versions and metadata
+    /// (such as authentication credentials) a [`BoltConnectionManager`] should use.
+    ///
+    /// [`BoltConnectionManager`]: crate::BoltConnectionManager
+    pub fn new(
+        addr: impl Into<String>,
+        preferred_versions: impl Into<[u32; 4]>,
+        metadata: HashMap<impl Into<String>, impl Into<Value>>,
+    ) -> Self {
+        Self {
+            addr: addr.into(),
+            domain: None,
+            preferred_versions: preferred_versions.into(),
+            metadata: metadata
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+            encryption: None,
+            connect_timeout: None,
+            keepalive: None,
+            resolver: None,
+        }
+    }
+
+    /// Sets the domain used for TLS, e.g. for certificate hostname verification.
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// Sets the TLS/encryption mode explicitly. Without this call, encryption is inferred from
+    /// whether [`domain`] was set, matching [`BoltConnectionManager::new`]'s historical behavior.
+    ///
+    /// [`domain`]: BoltConnectionManagerBuilder::domain
+    /// [`BoltConnectionManager::new`]: crate::BoltConnectionManager::new
+    pub fn encryption(mut self, encryption: Encryption) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
+
+    /// Bounds how long a single connection attempt (socket connect plus handshake) may take
+    /// before it is abandoned.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the TCP keepalive interval applied to each connection once established.
+    pub fn keepalive(mut self, interval: Duration) -> Self {
+        self.keepalive = Some(interval);
+        self
+    }
+
+    /// Overrides the DNS resolution used to turn `addr` into socket addresses; see
+    /// [`BoltConnectionManager::with_resolver`].
+    ///
+    /// [`BoltConnectionManager::with_resolver`]: crate::BoltConnectionManager::with_resolver
+    pub fn resolver(mut self, resolver: Arc<dyn Resolve>) -> Self {
+        self.resolver = Some(resolver);
+        self
+    }
+
+    /// Builds the [`BoltConnectionManager`].
+    ///
+    /// [`BoltConnectionManager`]: crate::BoltConnectionManager
+    pub fn build(self) -> BoltConnectionManager {
+        BoltConnectionManager {
+            target: Target::Direct {
+                host: self.addr,
+                resolver: self.resolver.unwrap_or_else(|| Arc::new(DefaultResolver)),
+            },
+            domain: self.domain,
+            preferred_versions: self.preferred_versions,
+            metadata: self.metadata,
+            access_mode: AccessMode::Write,
+            encryption: self.encryption,
+            connect_timeout: self.connect_timeout,
+            keepalive: self.keepalive,
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use bolt_proto::Message;
+use thiserror::Error as ThisError;
+
+/// Errors that can occur while managing or validating Bolt connections.
+#[derive(Debug, ThisError)]
+pub enum Error {
+    /// An address did not resolve to anything: a seed router passed to
+    /// [`BoltConnectionManager::new_routing`], or the host of a direct manager, which is resolved
+    /// on every connection attempt.
+    ///
+    /// [`BoltConnectionManager::new_routing`]: crate::BoltConnectionManager::new_routing
+    #[error("address did not resolve to any socket address")]
+    InvalidAddress,
+
+    /// The metadata supplied to the manager was missing a required key or held a value of
+    /// the wrong type for it.
+    #[error("invalid metadata: {metadata}")]
+    InvalidMetadata { metadata: String },
+
+    /// The connection negotiated a Bolt version the manager does not know how to drive.
+    #[error("unsupported Bolt client version: {version:#x}")]
+    InvalidClientVersion { version: u32 },
+
+    /// The server rejected `HELLO`/`INIT`, returning something other than `SUCCESS`.
+    #[error("failed to initialize client: {message:?}")]
+    ClientInitFailed { message: Message },
+
+    /// Failed to establish or negotiate the underlying connection.
+    #[error(transparent)]
+    BoltClient(#[from] bolt_client::error::Error),
+
+    /// A value returned by the server could not be converted to the expected Rust type.
+    #[error(transparent)]
+    BoltProto(#[from] bolt_proto::error::Error),
+
+    /// The underlying socket or DNS resolution failed.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// A routing table could not be parsed from the server's response to `ROUTE` or
+    /// `dbms.cluster.routing.getRoutingTable`.
+    #[error("could not parse routing table from server response")]
+    InvalidRoutingTable,
+
+    /// No router in the current routing table (or seed list) could be reached to fetch or
+    /// refresh a routing table.
+    #[error("no router is reachable to establish a routing table")]
+    NoRouterAvailable,
+
+    /// The current routing table has no server advertising the requested access mode.
+    #[error("routing table has no server for the requested access mode")]
+    NoServerForAccessMode,
+
+    /// A connection attempt did not complete within the configured connect timeout.
+    #[error("connection attempt timed out")]
+    ConnectTimeout,
+
+    /// [`Encryption::SystemTrust`]/[`Encryption::SystemTrustWithRoots`] was configured without a
+    /// `domain`, but a domain is required for certificate hostname verification.
+    ///
+    /// [`Encryption::SystemTrust`]: crate::Encryption::SystemTrust
+    /// [`Encryption::SystemTrustWithRoots`]: crate::Encryption::SystemTrustWithRoots
+    #[error("TLS was requested but no domain was configured for certificate verification")]
+    MissingTlsDomain,
+}
@@ -3,23 +3,49 @@
 use async_trait::async_trait;
 use bolt_client::{Client, Metadata, Stream};
 use bolt_proto::message::Success;
-use bolt_proto::version::{V1_0, V2_0, V3_0, V4_0, V4_1};
-use bolt_proto::{Message, Value};
+use bolt_proto::version::{
+    V1_0, V2_0, V3_0, V4_0, V4_1, V4_2, V4_3, V4_4, V5_0, V5_1, V5_2, V5_3, V5_4,
+};
+use bolt_proto::{Message, ServerState, Value};
 use mobc::Manager;
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::iter::FromIterator;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use tokio::io::BufStream;
 use tokio::net::lookup_host;
 use tokio::net::ToSocketAddrs;
 use tokio_util::compat::*;
 
+pub use config::{BoltConnectionManagerBuilder, Encryption};
 pub use error::Error;
+pub use resolve::Resolve;
+pub use routing::AccessMode;
+pub use version::PreferredVersions;
 
+#[cfg(feature = "bb8")]
+mod bb8_manager;
+mod config;
 mod error;
+mod resolve;
+mod routing;
+mod version;
+
+use resolve::DefaultResolver;
+use routing::RoutingState;
+use std::time::Duration;
+
+/// Bolt 5.1 split authentication out of `HELLO` into its own `LOGON`/`LOGOFF` messages; this
+/// reports whether `version` is new enough that callers should use that split instead of
+/// passing credentials directly to `hello`.
+pub(crate) fn version_uses_logon(version: u32) -> bool {
+    matches!(version, V5_1 | V5_2 | V5_3 | V5_4)
+}
 
 /// A Bolt connection manager, used by mobc to create and test the health of database connections.
+/// With the `bb8` feature enabled, it also implements `bb8::ManageConnection`, so the same
+/// manager works with either pool.
 ///
 /// # Examples
 ///
@@ -52,10 +78,26 @@ mod error;
 /// # }
 /// ```
 pub struct BoltConnectionManager {
-    addr: SocketAddr,
+    target: Target,
     domain: Option<String>,
     preferred_versions: [u32; 4],
     metadata: HashMap<String, Value>,
+    access_mode: AccessMode,
+    encryption: Option<Encryption>,
+    connect_timeout: Option<Duration>,
+    keepalive: Option<Duration>,
+}
+
+/// Where [`BoltConnectionManager::connect`] should open its socket: either a host resolved,
+/// fresh, on every connection attempt, or a causal cluster reached through a periodically
+/// refreshed routing table.
+#[derive(Clone)]
+enum Target {
+    Direct {
+        host: String,
+        resolver: Arc<dyn Resolve>,
+    },
+    Routed(Arc<RoutingState>),
 }
 
 impl BoltConnectionManager {
@@ -63,6 +105,10 @@ impl BoltConnectionManager {
     /// applicable the domain, of the database, preferred versions, and a hash map of metadata,
     /// such as authentication credentials.
     ///
+    /// `preferred_versions` accepts a bare `[u32; 4]` of per-version handshake slots as before,
+    /// or a [`PreferredVersions`] built from a list of `(major, minor)` pairs if you want the
+    /// Bolt 4.3+ range-encoded slots (e.g. to offer 5.0 through 5.4 in a single slot).
+    ///
     /// [`BoltConnectionManager`]: ./struct.BoltConnectionManager.html
     ///
     /// # Examples
@@ -96,24 +142,212 @@ impl BoltConnectionManager {
     /// # }
     /// ```
     pub async fn new(
-        addr: impl ToSocketAddrs,
+        addr: impl Into<String>,
         domain: Option<String>,
-        preferred_versions: [u32; 4],
+        preferred_versions: impl Into<[u32; 4]>,
         metadata: HashMap<impl Into<String>, impl Into<Value>>,
     ) -> Result<Self, Error> {
+        let mut builder = BoltConnectionManagerBuilder::new(addr, preferred_versions, metadata);
+        if let Some(domain) = domain {
+            builder = builder.domain(domain);
+        }
+        Ok(builder.build())
+    }
+
+    /// Overrides the DNS resolution used for this manager's direct (non-routing) connection
+    /// attempts; has no effect on a manager constructed with [`new_routing`], whose seed routers
+    /// are resolved independently. Useful behind service discovery or split-horizon DNS, where
+    /// [`tokio::net::lookup_host`] isn't the right way to turn a host into addresses.
+    ///
+    /// [`new_routing`]: BoltConnectionManager::new_routing
+    pub fn with_resolver(mut self, resolver: Arc<dyn Resolve>) -> Self {
+        if let Target::Direct { resolver: slot, .. } = &mut self.target {
+            *slot = resolver;
+        }
+        self
+    }
+
+    /// Overrides the TLS/encryption mode for this manager's connection attempts, including the
+    /// routing table fetches of a manager constructed with [`new_routing`]. See
+    /// [`BoltConnectionManagerBuilder::encryption`] for the same option at construction time.
+    ///
+    /// [`new_routing`]: BoltConnectionManager::new_routing
+    /// [`BoltConnectionManagerBuilder::encryption`]: crate::BoltConnectionManagerBuilder::encryption
+    pub fn with_encryption(mut self, encryption: Encryption) -> Self {
+        self.encryption = Some(encryption);
+        self
+    }
+
+    /// Overrides how long a single connection attempt (socket connect plus handshake) may take
+    /// before it is abandoned, including the routing table fetches of a manager constructed with
+    /// [`new_routing`].
+    ///
+    /// [`new_routing`]: BoltConnectionManager::new_routing
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Overrides the TCP keepalive interval applied to each connection once established.
+    pub fn with_keepalive(mut self, interval: Duration) -> Self {
+        self.keepalive = Some(interval);
+        self
+    }
+
+    /// Creates a new [`BoltConnectionManager`] that operates in routing mode against a causal
+    /// cluster, the way a full driver's `neo4j://` connections do. `seed_routers` are tried, in
+    /// order, to fetch the initial routing table; the table is then refreshed automatically once
+    /// its TTL expires, or sooner if a member it named turns out to be unreachable.
+    ///
+    /// `access_mode` picks which role (`READ` or `WRITE`) this manager draws connections for;
+    /// construct two managers sharing the same seed routers to get separate read and write pools
+    /// the way a production deployment typically would.
+    ///
+    /// `encryption`/`connect_timeout`/`keepalive` start unset; configure them afterwards with
+    /// [`with_encryption`], [`with_connect_timeout`], and [`with_keepalive`]. Encryption and the
+    /// connect timeout apply to every connection attempt this manager makes, including the
+    /// routing table fetches against `seed_routers` and any cluster member they return; the
+    /// keepalive interval is applied only to the connections handed out to the pool.
+    ///
+    /// [`with_encryption`]: BoltConnectionManager::with_encryption
+    /// [`with_connect_timeout`]: BoltConnectionManager::with_connect_timeout
+    /// [`with_keepalive`]: BoltConnectionManager::with_keepalive
+    ///
+    /// [`BoltConnectionManager`]: ./struct.BoltConnectionManager.html
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use bolt_proto::version::V4_1;
+    /// # use mobc::{Manager, Pool};
+    /// # use mobc_boltrs::{AccessMode, BoltConnectionManager};
+    /// # use std::collections::HashMap;
+    /// # use std::iter::FromIterator;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let manager = BoltConnectionManager::new_routing(
+    ///         vec!["localhost:7687", "localhost:7688"],
+    ///         None,
+    ///         [V4_1, 0, 0, 0],
+    ///         HashMap::from_iter(vec![
+    ///             ("user_agent", "bolt-client/X.Y.Z"),
+    ///             ("scheme", "basic"),
+    ///             ("principal", "username"),
+    ///             ("credentials", "password"),
+    ///         ]),
+    ///         AccessMode::Write,
+    ///     )
+    ///     .await?;
+    ///
+    ///     let pool = Pool::builder().max_open(20).build(manager);
+    ///     let client = pool.get().await?;
+    ///
+    /// #   Ok(())
+    /// # }
+    /// ```
+    pub async fn new_routing(
+        seed_routers: Vec<impl ToSocketAddrs>,
+        domain: Option<String>,
+        preferred_versions: impl Into<[u32; 4]>,
+        metadata: HashMap<impl Into<String>, impl Into<Value>>,
+        access_mode: AccessMode,
+    ) -> Result<Self, Error> {
+        let mut resolved = Vec::with_capacity(seed_routers.len());
+        for seed in seed_routers {
+            resolved.push(
+                lookup_host(seed)
+                    .await?
+                    .next()
+                    .ok_or(Error::InvalidAddress)?,
+            );
+        }
         Ok(Self {
-            addr: lookup_host(addr)
-                .await?
-                .next()
-                .ok_or(Error::InvalidAddress)?,
+            target: Target::Routed(Arc::new(RoutingState::new(resolved))),
             domain,
-            preferred_versions,
+            preferred_versions: preferred_versions.into(),
             metadata: metadata
                 .into_iter()
                 .map(|(k, v)| (k.into(), v.into()))
                 .collect(),
+            access_mode,
+            encryption: None,
+            connect_timeout: None,
+            keepalive: None,
         })
     }
+
+    /// Returns a sibling manager for `access_mode`, sharing this one's routing table cache.
+    /// Has no effect beyond the access mode itself on a manager constructed with [`new`], since
+    /// a direct connection has no routing table to share.
+    ///
+    /// [`new`]: BoltConnectionManager::new
+    pub fn with_access_mode(&self, access_mode: AccessMode) -> Self {
+        Self {
+            target: self.target.clone(),
+            domain: self.domain.clone(),
+            preferred_versions: self.preferred_versions,
+            metadata: self.metadata.clone(),
+            access_mode,
+            encryption: self.encryption.clone(),
+            connect_timeout: self.connect_timeout,
+            keepalive: self.keepalive,
+        }
+    }
+
+    /// Resolves the effective TLS domain for a connection attempt. Without an explicit
+    /// [`Encryption`], this infers encryption from whether a domain was configured at all, for
+    /// backward compatibility with [`BoltConnectionManager::new`]. With an explicit
+    /// [`Encryption::Disabled`], returns `None` regardless of `domain`. With an explicit
+    /// [`Encryption::SystemTrust`] or [`Encryption::SystemTrustWithRoots`], a domain is required
+    /// for certificate hostname verification, so this fails fast with
+    /// [`Error::MissingTlsDomain`] rather than silently falling back to a plaintext connection.
+    fn tls_domain(&self) -> Result<Option<&str>, Error> {
+        match &self.encryption {
+            None => Ok(self.domain.as_deref()),
+            Some(Encryption::Disabled) => Ok(None),
+            Some(Encryption::SystemTrust) | Some(Encryption::SystemTrustWithRoots { .. }) => self
+                .domain
+                .as_deref()
+                .map(Some)
+                .ok_or(Error::MissingTlsDomain),
+        }
+    }
+
+    /// Additional DER-encoded root certificates to trust for this connection attempt, beyond
+    /// the platform's own trust store.
+    fn root_certificates(&self) -> &[Vec<u8>] {
+        match &self.encryption {
+            Some(Encryption::SystemTrustWithRoots { der_certificates }) => der_certificates,
+            _ => &[],
+        }
+    }
+
+    /// Returns the addresses to try a connection attempt against, in order. A direct manager
+    /// resolves `host` fresh on every call; a routing manager returns every server its routing
+    /// table currently advertises for `access_mode`, rotated round-robin, so a failed candidate
+    /// has siblings left to fall back to within the same `connect()` call.
+    ///
+    /// For a routing manager, fetching or refreshing the routing table itself is a connection
+    /// attempt like any other, so it honors the same `tls_domain()`/`root_certificates()`/
+    /// `connect_timeout` this manager was configured with, rather than bypassing them.
+    async fn target_addrs(&self) -> Result<Vec<SocketAddr>, Error> {
+        match &self.target {
+            Target::Direct { host, resolver } => resolver.resolve(host).await,
+            Target::Routed(routing) => {
+                routing
+                    .candidates(
+                        self.access_mode,
+                        self.tls_domain()?,
+                        self.root_certificates(),
+                        self.connect_timeout,
+                        &self.preferred_versions,
+                        &self.metadata,
+                    )
+                    .await
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -122,11 +356,55 @@ impl Manager for BoltConnectionManager {
     type Error = Error;
 
     async fn connect(&self) -> Result<Self::Connection, Self::Error> {
-        let mut client = Client::new(
-            BufStream::new(Stream::connect(self.addr, self.domain.as_ref()).await?).compat(),
-            &self.preferred_versions,
-        )
-        .await?;
+        let candidates = self.target_addrs().await?;
+        let domain = self.tls_domain()?;
+        let root_certificates = self.root_certificates();
+        let mut last_error = None;
+        for addr in &candidates {
+            let attempt = self.establish(*addr, domain, root_certificates);
+            let result = match self.connect_timeout {
+                Some(timeout) => match tokio::time::timeout(timeout, attempt).await {
+                    Ok(result) => result,
+                    Err(_) => Err(Error::ConnectTimeout),
+                },
+                None => attempt.await,
+            };
+            match result {
+                Ok(client) => return Ok(client),
+                Err(error) => {
+                    if let Target::Routed(routing) = &self.target {
+                        routing.evict(*addr).await;
+                    }
+                    last_error = Some(error);
+                }
+            }
+        }
+        Err(last_error.unwrap_or(Error::InvalidAddress))
+    }
+
+    async fn check(&self, mut conn: Self::Connection) -> Result<Self::Connection, Self::Error> {
+        self.validate(&mut conn).await?;
+        Ok(conn)
+    }
+}
+
+impl BoltConnectionManager {
+    /// Opens a socket to `addr` and drives it all the way through the Bolt handshake
+    /// (`HELLO`/`INIT`, and `LOGON` where the version splits it out), returning a ready-to-use
+    /// client. Covers everything a single connection attempt needs to do, so callers can bound
+    /// the whole sequence -- not just the socket connect -- with a single timeout.
+    async fn establish(
+        &self,
+        addr: SocketAddr,
+        domain: Option<&str>,
+        root_certificates: &[Vec<u8>],
+    ) -> Result<Client<Compat<BufStream<Stream>>>, Error> {
+        let stream = Stream::connect_with_roots(addr, domain, root_certificates).await?;
+        if let Some(interval) = self.keepalive {
+            stream.set_keepalive(Some(interval));
+        }
+        let mut client = Client::new(BufStream::new(stream).compat(), &self.preferred_versions)
+            .await?;
         let response = match client.version() {
             V1_0 | V2_0 => {
                 let mut metadata = self.metadata.clone();
@@ -138,11 +416,15 @@ impl Manager for BoltConnectionManager {
                     .map(String::try_from)??;
                 client.init(user_agent, Metadata::from(metadata)).await?
             }
-            V3_0 | V4_0 | V4_1 => {
+            V3_0 | V4_0 | V4_1 | V4_2 | V4_3 | V4_4 | V5_0 => {
                 client
                     .hello(Some(Metadata::from(self.metadata.clone())))
                     .await?
             }
+            V5_1 | V5_2 | V5_3 | V5_4 => {
+                client.hello(None).await?;
+                client.logon(Metadata::from(self.metadata.clone())).await?
+            }
             _ => {
                 return Err(Error::InvalidClientVersion {
                     version: client.version(),
@@ -156,10 +438,32 @@ impl Manager for BoltConnectionManager {
         }
     }
 
-    async fn check(&self, mut conn: Self::Connection) -> Result<Self::Connection, Self::Error> {
+    /// Recovers a connection left in `FAILED`/`INTERRUPTED` state with a `RESET` (`ack_failure`
+    /// for V1/V2), then validates it with a liveness query. Shared by the `mobc` [`Manager::check`]
+    /// impl above and the `bb8` `ManageConnection::is_valid` impl behind the `bb8` feature, since
+    /// both pools need the same answer to "is this connection still good".
+    async fn validate(&self, conn: &mut Client<Compat<BufStream<Stream>>>) -> Result<(), Error> {
+        match conn.server_state() {
+            ServerState::Failed | ServerState::Interrupted => {
+                let response = match conn.version() {
+                    V1_0 | V2_0 => conn.ack_failure().await?,
+                    V3_0 | V4_0 | V4_1 | V4_2 | V4_3 | V4_4 | V5_0 | V5_1 | V5_2 | V5_3 | V5_4 => {
+                        conn.reset().await?
+                    }
+                    _ => {
+                        return Err(Error::InvalidClientVersion {
+                            version: conn.version(),
+                        })
+                    }
+                };
+                Success::try_from(response)?;
+            }
+            _ => {}
+        }
+
         let response = match conn.version() {
             V1_0 | V2_0 => conn.run("RETURN 1;".to_string(), None).await?,
-            V3_0 | V4_0 | V4_1 => {
+            V3_0 | V4_0 | V4_1 | V4_2 | V4_3 | V4_4 | V5_0 | V5_1 | V5_2 | V5_3 | V5_4 => {
                 conn.run_with_metadata(
                     "RETURN 1;".to_string(),
                     None,
@@ -176,7 +480,7 @@ impl Manager for BoltConnectionManager {
         Success::try_from(response)?;
         let (response, _records) = match conn.version() {
             V1_0 | V2_0 | V3_0 => conn.pull_all().await?,
-            V4_0 | V4_1 => {
+            V4_0 | V4_1 | V4_2 | V4_3 | V4_4 | V5_0 | V5_1 | V5_2 | V5_3 | V5_4 => {
                 let pull_meta = Metadata::from_iter(vec![("n", -1)]);
                 conn.pull(Some(pull_meta)).await?
             }
@@ -187,7 +491,17 @@ impl Manager for BoltConnectionManager {
             }
         };
         Success::try_from(response)?;
-        Ok(conn)
+        Ok(())
+    }
+
+    /// Returns whether `conn` is in a state bb8 (or any pool) should discard rather than reuse:
+    /// `DEFUNCT` (the underlying socket is gone) or `DISCONNECTED`.
+    #[cfg(feature = "bb8")]
+    fn is_broken(conn: &Client<Compat<BufStream<Stream>>>) -> bool {
+        matches!(
+            conn.server_state(),
+            ServerState::Defunct | ServerState::Disconnected
+        )
     }
 }
 
@@ -206,7 +520,7 @@ mod tests {
     use std::iter::FromIterator;
 
     async fn get_connection_manager(
-        preferred_versions: [u32; 4],
+        preferred_versions: impl Into<[u32; 4]>,
         succeed: bool,
     ) -> BoltConnectionManager {
         let credentials = if succeed {
@@ -0,0 +1,34 @@
+//! Pluggable DNS resolution for [`BoltConnectionManager`]'s direct (non-routing) connections, so
+//! deployments behind service discovery or split-horizon DNS can resolve a host their own way
+//! instead of being locked to [`tokio::net::lookup_host`].
+//!
+//! [`BoltConnectionManager`]: crate::BoltConnectionManager
+
+use crate::Error;
+use async_trait::async_trait;
+use std::net::SocketAddr;
+use tokio::net::lookup_host;
+
+/// Resolves a `host:port` string to the socket addresses a connection attempt should try, in
+/// order. Called on every [`connect`], not cached by the caller, so an implementation that wants
+/// caching (or a fixed address list) needs to do that itself.
+///
+/// [`connect`]: mobc::Manager::connect
+#[async_trait]
+pub trait Resolve: Send + Sync {
+    async fn resolve(&self, host: &str) -> Result<Vec<SocketAddr>, Error>;
+}
+
+/// The default resolver, backed by [`tokio::net::lookup_host`]; used unless a manager is
+/// constructed with [`BoltConnectionManager::with_resolver`].
+///
+/// [`BoltConnectionManager::with_resolver`]: crate::BoltConnectionManager::with_resolver
+#[derive(Debug, Default)]
+pub(crate) struct DefaultResolver;
+
+#[async_trait]
+impl Resolve for DefaultResolver {
+    async fn resolve(&self, host: &str) -> Result<Vec<SocketAddr>, Error> {
+        Ok(lookup_host(host).await?.collect())
+    }
+}
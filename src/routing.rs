@@ -0,0 +1,472 @@
+//! Routing-table support for Neo4j causal clusters, i.e. what a full driver does for a
+//! `neo4j://`-scheme connection instead of a direct `bolt://` one.
+//!
+//! A [`RoutingTable`] is fetched against any member of the cluster, and is considered valid
+//! until its TTL elapses: a `ROUTE` message on Bolt 4.3+, or the
+//! `CALL dbms.cluster.routing.getRoutingTable` procedure on older versions. [`RoutingState`] owns
+//! the cached table behind a lock, refreshing it on expiry or on demand when a member turns out
+//! to be unreachable, and hands out servers for a given [`AccessMode`] round-robin.
+
+use crate::Error;
+use bolt_client::{Client, Metadata, Stream};
+use bolt_proto::message::Success;
+use bolt_proto::version::{V4_3, V4_4, V5_0, V5_1, V5_2, V5_3, V5_4};
+use bolt_proto::Value;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::iter::FromIterator;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio::io::BufStream;
+use tokio::net::lookup_host;
+use tokio::sync::Mutex;
+use tokio_util::compat::Compat;
+
+/// The connection type used while a routing table is being fetched from a cluster member; the
+/// same transport [`BoltConnectionManager::connect`] hands off to its own client.
+///
+/// [`BoltConnectionManager::connect`]: crate::BoltConnectionManager
+type RoutingClient = Client<Compat<BufStream<Stream>>>;
+
+/// Which role a connection drawn from a routing table should serve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessMode {
+    Read,
+    Write,
+}
+
+/// A parsed routing table, with the instant it was fetched so callers can tell when it needs
+/// refreshing.
+#[derive(Debug, Clone)]
+pub(crate) struct RoutingTable {
+    pub(crate) routers: Vec<SocketAddr>,
+    pub(crate) readers: Vec<SocketAddr>,
+    pub(crate) writers: Vec<SocketAddr>,
+    ttl: Duration,
+    fetched_at: Instant,
+}
+
+impl RoutingTable {
+    fn is_expired(&self) -> bool {
+        Instant::now() > self.fetched_at + self.ttl
+    }
+
+    fn servers(&self, mode: AccessMode) -> &[SocketAddr] {
+        match mode {
+            AccessMode::Read => &self.readers,
+            AccessMode::Write => &self.writers,
+        }
+    }
+
+    /// Parses the single record returned by `dbms.cluster.routing.getRoutingTable`: a `ttl`
+    /// (seconds) field followed by a `servers` field, a list of `{addresses, role}` maps.
+    ///
+    /// Each advertised address is resolved with [`lookup_host`], keeping only the first
+    /// result, the same way [`BoltConnectionManager::new_routing`] resolves its seed routers.
+    ///
+    /// [`BoltConnectionManager::new_routing`]: crate::BoltConnectionManager::new_routing
+    async fn try_parse(fields: Vec<Value>) -> Result<Self, Error> {
+        let mut fields = fields.into_iter();
+        let ttl_seconds = match fields.next() {
+            Some(Value::Integer(seconds)) => seconds,
+            _ => return Err(Error::InvalidRoutingTable),
+        };
+        let servers = match fields.next() {
+            Some(Value::List(servers)) => servers,
+            _ => return Err(Error::InvalidRoutingTable),
+        };
+
+        let mut routers = Vec::new();
+        let mut readers = Vec::new();
+        let mut writers = Vec::new();
+
+        for server in servers {
+            let mut entry = match server {
+                Value::Map(entry) => entry,
+                _ => return Err(Error::InvalidRoutingTable),
+            };
+            let role = match entry.remove("role") {
+                Some(Value::String(role)) => role,
+                _ => return Err(Error::InvalidRoutingTable),
+            };
+            let addresses = match entry.remove("addresses") {
+                Some(Value::List(addresses)) => addresses,
+                _ => return Err(Error::InvalidRoutingTable),
+            };
+
+            let bucket = match role.as_str() {
+                "ROUTE" => &mut routers,
+                "READ" => &mut readers,
+                "WRITE" => &mut writers,
+                _ => continue,
+            };
+            for address in addresses {
+                let address = match address {
+                    Value::String(address) => address,
+                    _ => return Err(Error::InvalidRoutingTable),
+                };
+                if let Some(resolved) = lookup_host(address).await?.next() {
+                    bucket.push(resolved);
+                }
+            }
+        }
+
+        Ok(Self {
+            routers,
+            readers,
+            writers,
+            ttl: Duration::from_secs(ttl_seconds.max(0) as u64),
+            fetched_at: Instant::now(),
+        })
+    }
+}
+
+/// Shared, lock-guarded routing state for a [`BoltConnectionManager`] operating against a
+/// causal cluster: the seed routers it was constructed with, the most recently fetched
+/// [`RoutingTable`], and a round-robin cursor per access mode.
+///
+/// [`BoltConnectionManager`]: crate::BoltConnectionManager
+#[derive(Debug)]
+pub(crate) struct RoutingState {
+    seed_routers: Vec<SocketAddr>,
+    table: Mutex<Option<RoutingTable>>,
+    next_reader: AtomicUsize,
+    next_writer: AtomicUsize,
+    next_router: AtomicUsize,
+}
+
+impl RoutingState {
+    pub(crate) fn new(seed_routers: Vec<SocketAddr>) -> Self {
+        Self {
+            seed_routers,
+            table: Mutex::new(None),
+            next_reader: AtomicUsize::new(0),
+            next_writer: AtomicUsize::new(0),
+            next_router: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns every server currently advertising `mode`, fetching or refreshing the routing
+    /// table first if it is missing or expired, rotated round-robin so repeated calls spread
+    /// load across them. The caller tries them in the returned order, falling back to the next
+    /// one if an earlier candidate's connection attempt fails.
+    ///
+    /// The expiry check and the fetch are deliberately two separate lock acquisitions: `table`
+    /// is `tokio::sync::Mutex`, which is not reentrant, and [`fetch_table`] takes the same lock
+    /// internally to snapshot the current router list. Holding a guard here across the `.await`
+    /// on [`fetch_table`] would deadlock the very first call, since the inner lock attempt could
+    /// never be granted.
+    ///
+    /// [`fetch_table`]: RoutingState::fetch_table
+    pub(crate) async fn candidates(
+        &self,
+        mode: AccessMode,
+        domain: Option<&str>,
+        root_certificates: &[Vec<u8>],
+        connect_timeout: Option<Duration>,
+        preferred_versions: &[u32; 4],
+        metadata: &HashMap<String, Value>,
+    ) -> Result<Vec<SocketAddr>, Error> {
+        let needs_fetch = {
+            let table = self.table.lock().await;
+            table.is_none() || table.as_ref().unwrap().is_expired()
+        };
+        if needs_fetch {
+            let fresh = self
+                .fetch_table(
+                    domain,
+                    root_certificates,
+                    connect_timeout,
+                    preferred_versions,
+                    metadata,
+                )
+                .await?;
+            *self.table.lock().await = Some(fresh);
+        }
+
+        let table = self.table.lock().await;
+        let servers = table.as_ref().unwrap().servers(mode);
+        if servers.is_empty() {
+            return Err(Error::NoServerForAccessMode);
+        }
+        let cursor = match mode {
+            AccessMode::Read => &self.next_reader,
+            AccessMode::Write => &self.next_writer,
+        };
+        let start = cursor.fetch_add(1, Ordering::Relaxed) % servers.len();
+        Ok((0..servers.len())
+            .map(|offset| servers[(start + offset) % servers.len()])
+            .collect())
+    }
+
+    /// Drops `unreachable` from the cached table's router/reader/writer lists so the next
+    /// [`candidates`] or [`fetch_table`] call doesn't pick it again, and marks the table expired
+    /// so the next [`candidates`] call re-routes with a fresh table instead of waiting out the
+    /// TTL. `seed_routers` itself is never mutated: it's the fixed bootstrap list a production
+    /// driver falls back to once the fetched table's own routers are all gone, so a seed that's
+    /// down today can still be retried once the table's routers have been exhausted, the same
+    /// way [`routers`] already falls back to it.
+    ///
+    /// [`candidates`]: RoutingState::candidates
+    /// [`fetch_table`]: RoutingState::fetch_table
+    /// [`routers`]: RoutingState::routers
+    pub(crate) async fn evict(&self, unreachable: SocketAddr) {
+        let mut table = self.table.lock().await;
+        if let Some(current) = table.as_mut() {
+            current.routers.retain(|addr| *addr != unreachable);
+            current.readers.retain(|addr| *addr != unreachable);
+            current.writers.retain(|addr| *addr != unreachable);
+            current.ttl = Duration::from_secs(0);
+        }
+    }
+
+    fn routers<'a>(&'a self, table: &'a Option<RoutingTable>) -> &'a [SocketAddr] {
+        match table {
+            Some(table) if !table.routers.is_empty() => &table.routers,
+            _ => &self.seed_routers,
+        }
+    }
+
+    async fn fetch_table(
+        &self,
+        domain: Option<&str>,
+        root_certificates: &[Vec<u8>],
+        connect_timeout: Option<Duration>,
+        preferred_versions: &[u32; 4],
+        metadata: &HashMap<String, Value>,
+    ) -> Result<RoutingTable, Error> {
+        let routers = {
+            let table = self.table.lock().await;
+            self.routers(&table).to_vec()
+        };
+        if routers.is_empty() {
+            return Err(Error::NoRouterAvailable);
+        }
+
+        let start = self.next_router.fetch_add(1, Ordering::Relaxed) % routers.len();
+        let mut last_error = None;
+        for offset in 0..routers.len() {
+            let router = routers[(start + offset) % routers.len()];
+            let attempt = Self::fetch_table_from(
+                router,
+                domain,
+                root_certificates,
+                preferred_versions,
+                metadata,
+            );
+            let result = match connect_timeout {
+                Some(timeout) => match tokio::time::timeout(timeout, attempt).await {
+                    Ok(result) => result,
+                    Err(_) => Err(Error::ConnectTimeout),
+                },
+                None => attempt.await,
+            };
+            match result {
+                Ok(table) => return Ok(table),
+                Err(error) => {
+                    self.evict(router).await;
+                    last_error = Some(error);
+                }
+            }
+        }
+        Err(last_error.unwrap_or(Error::NoRouterAvailable))
+    }
+
+    /// Connects to `router` and fetches a routing table from it: socket connect plus handshake
+    /// plus the `ROUTE`/procedure round-trip. [`fetch_table`] wraps the whole call in
+    /// `connect_timeout`, the same way the manager's `connect` bounds its call to
+    /// [`BoltConnectionManager::establish`] for the final data connection.
+    ///
+    /// [`fetch_table`]: RoutingState::fetch_table
+    /// [`BoltConnectionManager::establish`]: crate::BoltConnectionManager
+    async fn fetch_table_from(
+        router: SocketAddr,
+        domain: Option<&str>,
+        root_certificates: &[Vec<u8>],
+        preferred_versions: &[u32; 4],
+        metadata: &HashMap<String, Value>,
+    ) -> Result<RoutingTable, Error> {
+        use tokio_util::compat::*;
+
+        let mut client: RoutingClient = Client::new(
+            BufStream::new(Stream::connect_with_roots(router, domain, root_certificates).await?)
+                .compat(),
+            preferred_versions,
+        )
+        .await?;
+        if crate::version_uses_logon(client.version()) {
+            client.hello(None).await?;
+            client
+                .logon(Metadata::from(metadata.clone()))
+                .await?;
+        } else {
+            client
+                .hello(Some(Metadata::from(metadata.clone())))
+                .await?;
+        }
+
+        let fields = if matches!(
+            client.version(),
+            V4_3 | V4_4 | V5_0 | V5_1 | V5_2 | V5_3 | V5_4
+        ) {
+            Self::fetch_table_fields_via_route(&mut client).await?
+        } else {
+            Self::fetch_table_fields_via_procedure(&mut client).await?
+        };
+
+        RoutingTable::try_parse(fields).await
+    }
+
+    /// Bolt 4.3+: asks for a routing table with a `ROUTE` message, whose `SUCCESS` carries the
+    /// table directly under the `rt` metadata key as a `{ttl, servers}` map, rather than as a
+    /// queried and pulled record.
+    ///
+    /// `routing_context` is a distinct Bolt concept from the `HELLO`/`LOGON` auth metadata --
+    /// small policy tags (e.g. region, `policy`) a server's routing plugin can key off of, not a
+    /// place to resend credentials -- so it's always sent empty here rather than reusing
+    /// `metadata`.
+    async fn fetch_table_fields_via_route(client: &mut RoutingClient) -> Result<Vec<Value>, Error> {
+        let routing_context = Metadata::from(HashMap::<String, Value>::new());
+        let response = client.route(routing_context, Vec::new(), None).await?;
+        let success = Success::try_from(response)?;
+        let mut table = match success.metadata().get("rt") {
+            Some(Value::Map(table)) => table.clone(),
+            _ => return Err(Error::InvalidRoutingTable),
+        };
+        let ttl = table.remove("ttl").ok_or(Error::InvalidRoutingTable)?;
+        let servers = table.remove("servers").ok_or(Error::InvalidRoutingTable)?;
+        Ok(vec![ttl, servers])
+    }
+
+    /// Pre-4.3: asks for a routing table by running
+    /// `CALL dbms.cluster.routing.getRoutingTable`, the same way any other Cypher query would be
+    /// issued and pulled.
+    async fn fetch_table_fields_via_procedure(
+        client: &mut RoutingClient,
+    ) -> Result<Vec<Value>, Error> {
+        let context = Value::from(HashMap::<String, Value>::new());
+        let params = Metadata::from_iter(vec![("context", context)]);
+        let response = client
+            .run_with_metadata(
+                "CALL dbms.cluster.routing.getRoutingTable($context);".to_string(),
+                Some(params),
+                None,
+            )
+            .await?;
+        Success::try_from(response)?;
+
+        let pull_meta = Metadata::from_iter(vec![("n", -1)]);
+        let (response, records) = client.pull(Some(pull_meta)).await?;
+        Success::try_from(response)?;
+
+        let record = records.into_iter().next().ok_or(Error::InvalidRoutingTable)?;
+        Ok(record.fields().to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AccessMode, RoutingState, RoutingTable};
+    use crate::Error;
+    use bolt_proto::Value;
+    use std::collections::HashMap;
+    use std::iter::FromIterator;
+    use std::time::Duration;
+
+    fn server(role: &str, addresses: &[&str]) -> Value {
+        Value::Map(HashMap::from_iter(vec![
+            ("role".to_string(), Value::String(role.to_string())),
+            (
+                "addresses".to_string(),
+                Value::List(
+                    addresses
+                        .iter()
+                        .map(|addr| Value::String(addr.to_string()))
+                        .collect(),
+                ),
+            ),
+        ]))
+    }
+
+    #[tokio::test]
+    async fn parses_ttl_and_role_buckets() {
+        let fields = vec![
+            Value::Integer(300),
+            Value::List(vec![
+                server("ROUTE", &["127.0.0.1:7687"]),
+                server("READ", &["127.0.0.1:7688", "127.0.0.1:7689"]),
+                server("WRITE", &["127.0.0.1:7687"]),
+            ]),
+        ];
+
+        let table = RoutingTable::try_parse(fields).await.unwrap();
+        assert_eq!(table.ttl, std::time::Duration::from_secs(300));
+        assert_eq!(table.routers.len(), 1);
+        assert_eq!(table.readers.len(), 2);
+        assert_eq!(table.writers.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn unknown_role_is_ignored() {
+        let fields = vec![
+            Value::Integer(60),
+            Value::List(vec![server("ADMIN", &["127.0.0.1:7687"])]),
+        ];
+
+        let table = RoutingTable::try_parse(fields).await.unwrap();
+        assert!(table.routers.is_empty());
+        assert!(table.readers.is_empty());
+        assert!(table.writers.is_empty());
+    }
+
+    #[tokio::test]
+    async fn negative_ttl_is_clamped_to_zero() {
+        let fields = vec![Value::Integer(-1), Value::List(vec![])];
+
+        let table = RoutingTable::try_parse(fields).await.unwrap();
+        assert_eq!(table.ttl, std::time::Duration::from_secs(0));
+    }
+
+    #[tokio::test]
+    async fn missing_ttl_field_is_invalid() {
+        let fields = vec![Value::List(vec![])];
+
+        let error = RoutingTable::try_parse(fields).await.unwrap_err();
+        assert!(matches!(error, Error::InvalidRoutingTable));
+    }
+
+    #[tokio::test]
+    async fn malformed_server_entry_is_invalid() {
+        let fields = vec![
+            Value::Integer(300),
+            Value::List(vec![Value::String("not a map".to_string())]),
+        ];
+
+        let error = RoutingTable::try_parse(fields).await.unwrap_err();
+        assert!(matches!(error, Error::InvalidRoutingTable));
+    }
+
+    /// Drives `candidates()` -> `fetch_table()` -> `fetch_table_from()` end to end against a
+    /// seed router nothing is listening on. With no mock server available in this crate, the
+    /// connection attempt itself is expected to fail fast (connection refused); what this test
+    /// actually guards against is the table lock being held across that attempt: before the fix,
+    /// `candidates()` held its lock across the `.await` on `fetch_table()`, which re-locks the
+    /// same non-reentrant mutex internally, hanging forever on the very first call. The
+    /// surrounding timeout turns that regression into a fast failure instead of a hang.
+    #[tokio::test]
+    async fn candidates_does_not_deadlock_on_first_fetch() {
+        let unreachable = "127.0.0.1:1".parse().unwrap();
+        let state = RoutingState::new(vec![unreachable]);
+        let metadata = HashMap::new();
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            state.candidates(AccessMode::Read, None, &[], None, &[0, 0, 0, 0], &metadata),
+        )
+        .await;
+
+        assert!(result.is_ok(), "candidates() did not return within the timeout");
+        assert!(result.unwrap().is_err());
+    }
+}
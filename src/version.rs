@@ -0,0 +1,102 @@
+//! Builds the preferred-versions handshake slots [`BoltConnectionManager`] offers a server,
+//! including Bolt 4.3+'s range-encoded slots that let one slot advertise a contiguous run of
+//! minor versions instead of needing one slot per minor version.
+//!
+//! [`BoltConnectionManager`]: crate::BoltConnectionManager
+
+/// The four handshake slots offered to a server during the Bolt version negotiation.
+///
+/// Each slot mirrors the layout of `bolt_proto`'s own per-version constants (e.g. `V4_1`): byte
+/// 0 is always zero, byte 1 is the range (how many additional, lower minor versions within the
+/// same major version are also acceptable), byte 2 is the minor version, and byte 3 is the major
+/// version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PreferredVersions([u32; 4]);
+
+impl PreferredVersions {
+    /// Builds up to four slots from `versions`, a list of acceptable `(major, minor)` pairs in
+    /// any order. Versions are grouped by major version and, within each group, folded into the
+    /// smallest number of contiguous minor-version ranges, highest first; anything past the
+    /// fourth slot is dropped, as only four are ever sent.
+    pub fn from_versions(versions: &[(u8, u8)]) -> Self {
+        let mut sorted = versions.to_vec();
+        sorted.sort_unstable_by(|a, b| b.cmp(a));
+        sorted.dedup();
+
+        let mut slots: Vec<(u8, u8, u8)> = Vec::new();
+        for (major, minor) in sorted {
+            if let Some((slot_major, top_minor, range)) = slots.last_mut() {
+                if *slot_major == major && minor + *range + 1 == *top_minor {
+                    *range += 1;
+                    continue;
+                }
+            }
+            if slots.len() == 4 {
+                break;
+            }
+            slots.push((major, minor, 0));
+        }
+
+        let mut encoded = [0u32; 4];
+        for (slot, (major, minor, range)) in slots.into_iter().enumerate() {
+            encoded[slot] = u32::from_be_bytes([0, range, minor, major]);
+        }
+        Self(encoded)
+    }
+}
+
+impl From<PreferredVersions> for [u32; 4] {
+    fn from(versions: PreferredVersions) -> Self {
+        versions.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PreferredVersions;
+
+    fn slots(versions: PreferredVersions) -> [u32; 4] {
+        versions.into()
+    }
+
+    #[test]
+    fn single_version() {
+        assert_eq!(
+            slots(PreferredVersions::from_versions(&[(4, 1)])),
+            [0x0000_0104, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn contiguous_minor_versions_fold_into_one_slot() {
+        assert_eq!(
+            slots(PreferredVersions::from_versions(&[(5, 0), (5, 1), (5, 2), (5, 3), (5, 4)])),
+            [0x0004_0405, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn non_contiguous_gap_uses_separate_slots() {
+        assert_eq!(
+            slots(PreferredVersions::from_versions(&[(4, 1), (4, 3), (4, 4)])),
+            [0x0001_0404, 0x0000_0104, 0, 0]
+        );
+    }
+
+    #[test]
+    fn more_than_four_groups_drops_the_lowest() {
+        let versions = [(5, 0), (4, 0), (3, 0), (2, 0), (1, 0)];
+        assert_eq!(
+            slots(PreferredVersions::from_versions(&versions)),
+            [0x0000_0005, 0x0000_0004, 0x0000_0003, 0x0000_0002]
+        );
+    }
+
+    #[test]
+    fn duplicate_versions_are_ignored() {
+        assert_eq!(
+            slots(PreferredVersions::from_versions(&[(4, 1), (4, 1)])),
+            [0x0000_0104, 0, 0, 0]
+        );
+    }
+}